@@ -1,8 +1,8 @@
 mod package;
 mod test;
 
-use package::Package;
-use std::collections::HashSet;
+use globset::{Glob, GlobSetBuilder};
+use package::{Format, Package};
 use std::path::PathBuf;
 use structopt::StructOpt;
 
@@ -12,31 +12,62 @@ struct Opt {
     #[structopt(parse(from_os_str), default_value = ".")]
     path: PathBuf,
 
+    /// Only walk these paths (relative to, or under, `path`) instead of all of `path`. Each one
+    /// is walked directly, so sibling subtrees are never even stat'd, unlike `--ignored-paths`
+    /// which still has to visit a directory before it can decide to prune it. Repeatable.
+    #[structopt(long, parse(from_os_str))]
+    include_paths: Vec<PathBuf>,
+
+    /// Gitignore-style glob patterns (e.g. `**/generated/*.rs`) of paths to prune from the
+    /// walk. A directory matching one of these is never descended into.
     #[structopt(long)]
     ignored_paths: Vec<String>,
 
     #[structopt(long)]
     ignored_features: Vec<String>,
+
+    /// Also report features declared in a Cargo.toml but never used anywhere in the crate.
+    #[structopt(long, visible_alias = "orphans")]
+    unused: bool,
+
+    /// Output format for the hidden-features report: "human" for text, "json" for CI
+    /// consumption.
+    #[structopt(long, default_value = "human")]
+    format: Format,
 }
 
 fn main() -> Result<(), String> {
     let opt = Opt::from_args();
 
-    let mut ignored_paths: HashSet<PathBuf> = opt
-        .ignored_paths
-        .iter()
-        .cloned()
-        .map(PathBuf::from)
-        .collect();
+    let mut ignored_paths_builder = GlobSetBuilder::new();
+    for pattern in &opt.ignored_paths {
+        let glob = Glob::new(pattern).map_err(|e| e.to_string())?;
+        ignored_paths_builder.add(glob);
+    }
 
-    // Ignore the "target" directoy.
-    ignored_paths.insert(opt.path.join("target"));
+    // Ignore the "target" directoy, wherever it shows up under the root.
+    let target_glob = Glob::new("**/target").map_err(|e| e.to_string())?;
+    ignored_paths_builder.add(target_glob);
+
+    let ignored_paths = ignored_paths_builder.build().map_err(|e| e.to_string())?;
 
     let ignored_features = opt.ignored_features.iter().cloned().collect();
 
     let mut package = Package::new(ignored_paths, ignored_features);
-    package.find_used_features(&opt.path)?;
-    package.find_exposed_features();
+    let roots = if opt.include_paths.is_empty() {
+        vec![opt.path.clone()]
+    } else {
+        opt.include_paths
+            .iter()
+            .map(|include_path| opt.path.join(include_path))
+            .collect()
+    };
+    for root in &roots {
+        package.find_used_features(root)?;
+    }
+    package.find_exposed_features()?;
     package.find_hidden_features();
-    package.check_hidden_features()
+    package.find_orphan_features();
+
+    package.check_all(opt.format, opt.unused)
 }
@@ -1,14 +1,151 @@
+use globset::GlobSet;
 use lazy_static::lazy_static;
+use rayon::prelude::*;
 use regex::Regex;
+use serde::Serialize;
 use std::cmp::{Eq, PartialEq};
 use std::collections::{HashMap, HashSet};
 use std::fs::read_to_string;
-use std::fs::File;
 use std::hash::{Hash, Hasher};
-use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use syn::visit::{self, Visit};
 use walkdir::{DirEntry, WalkDir};
 
+/// Output format for the hidden-features report.
+#[derive(Debug, Clone, Copy)]
+pub enum Format {
+    /// Human-readable text, printed to stdout.
+    Human,
+    /// A single JSON object, for CI jobs to parse and annotate PRs with.
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(Self::Human),
+            "json" => Ok(Self::Json),
+            other => Err(format!(
+                "unknown format {:?}, expected \"human\" or \"json\"",
+                other
+            )),
+        }
+    }
+}
+
+/// A single hidden-feature finding: a feature used in code but missing from the crate's
+/// Cargo.toml, along with where it was found.
+#[derive(Debug, Serialize)]
+pub struct HiddenFeatureEntry {
+    name: String,
+    file: Option<PathBuf>,
+    line: Option<u64>,
+}
+
+/// The hidden features found in one crate.
+#[derive(Debug, Serialize)]
+pub struct CrateReport {
+    cargo_toml: PathBuf,
+    hidden_features: Vec<HiddenFeatureEntry>,
+}
+
+/// The full hidden-features report across every crate in the package, built up front so it can
+/// be serialized to whichever format was requested, independently of the exit-code decision.
+#[derive(Debug, Serialize)]
+pub struct HiddenFeaturesReport {
+    crates: Vec<CrateReport>,
+}
+
+impl HiddenFeaturesReport {
+    /// Whether no crate in the report has any hidden features.
+    fn is_empty(&self) -> bool {
+        self.crates.iter().all(|c| c.hidden_features.is_empty())
+    }
+}
+
+/// A single orphan-feature finding: a feature exposed by a Cargo.toml but never used anywhere
+/// and never pulled in by another feature's dependency array.
+#[derive(Debug, Serialize)]
+pub struct OrphanFeatureEntry {
+    name: String,
+}
+
+/// The orphan features found in one crate.
+#[derive(Debug, Serialize)]
+pub struct CrateOrphanReport {
+    cargo_toml: PathBuf,
+    orphan_features: Vec<OrphanFeatureEntry>,
+}
+
+/// The full orphan-features report across every crate in the package, built up front so it can
+/// be serialized to whichever format was requested, independently of the exit-code decision.
+#[derive(Debug, Serialize)]
+pub struct OrphanFeaturesReport {
+    crates: Vec<CrateOrphanReport>,
+}
+
+impl OrphanFeaturesReport {
+    /// Whether no crate in the report has any orphan features.
+    fn is_empty(&self) -> bool {
+        self.crates.iter().all(|c| c.orphan_features.is_empty())
+    }
+}
+
+/// A single undefined-feature-reference finding: an entry in a feature's dependency array that
+/// doesn't resolve to anything declared in the manifest.
+#[derive(Debug, Serialize)]
+pub struct UndefinedReferenceEntry {
+    feature: String,
+    reference: String,
+}
+
+/// The undefined feature references found in one crate.
+#[derive(Debug, Serialize)]
+pub struct CrateReferenceReport {
+    cargo_toml: PathBuf,
+    undefined_references: Vec<UndefinedReferenceEntry>,
+}
+
+/// The full undefined-feature-reference report across every crate in the package, built up
+/// front so it can be serialized to whichever format was requested, independently of the
+/// exit-code decision.
+#[derive(Debug, Serialize)]
+pub struct FeatureReferencesReport {
+    crates: Vec<CrateReferenceReport>,
+}
+
+impl FeatureReferencesReport {
+    /// Whether no crate in the report has any undefined feature references.
+    fn is_empty(&self) -> bool {
+        self.crates.iter().all(|c| c.undefined_references.is_empty())
+    }
+}
+
+/// Every check's report combined into a single document, so that under `--format json` a CI job
+/// reading stdout gets exactly one JSON object back, regardless of how many checks are enabled.
+#[derive(Debug, Serialize)]
+pub struct FullReport {
+    hidden_features: HiddenFeaturesReport,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    orphan_features: Option<OrphanFeaturesReport>,
+    feature_references: FeatureReferencesReport,
+}
+
+impl FullReport {
+    /// Whether nothing in this report would fail the check.
+    fn is_ok(&self) -> bool {
+        self.hidden_features.is_empty()
+            && self
+                .orphan_features
+                .as_ref()
+                .is_none_or(OrphanFeaturesReport::is_empty)
+            && self.feature_references.is_empty()
+    }
+}
+
 /// Extracts the features from a given string and collects them into a Vector.
 /// e.g `"#[cfg(features = "foo", features= "bar")]"` -> `vec!["foo", "bar"]`
 fn extract_feature_names(line: &str) -> Option<Vec<&str>> {
@@ -28,6 +165,113 @@ fn extract_feature_names(line: &str) -> Option<Vec<&str>> {
     )
 }
 
+/// Walks a parsed `syn::File`, collecting every `feature = "..."` predicate it finds: inside
+/// `#[cfg(...)]`/`#[cfg_attr(...)]` attributes (including when nested inside `all(...)`,
+/// `any(...)` or `not(...)`), and inside `cfg!(...)` macro invocations. Unlike the line-based
+/// regex, this walks the real token tree, so it doesn't miss predicates split across lines and
+/// doesn't false-positive on `feature = "x"` written inside a string literal or a comment.
+#[derive(Default)]
+struct FeatureVisitor {
+    features: Vec<(String, u64)>,
+}
+
+impl FeatureVisitor {
+    /// Recursively scans a `cfg`-style meta (`all(...)`, `any(...)`, `not(...)`, or a bare
+    /// `feature = "..."`) for feature predicates.
+    fn visit_cfg_meta(&mut self, meta: &syn::Meta) {
+        match meta {
+            syn::Meta::NameValue(name_value) if name_value.path.is_ident("feature") => {
+                if let syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(name),
+                    ..
+                }) = &name_value.value
+                {
+                    self.features
+                        .push((name.value(), name.span().start().line as u64));
+                }
+            }
+            syn::Meta::List(list)
+                if list.path.is_ident("all")
+                    || list.path.is_ident("any")
+                    || list.path.is_ident("not") =>
+            {
+                if let Ok(nested) = list.parse_args_with(
+                    syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+                ) {
+                    for nested_meta in &nested {
+                        self.visit_cfg_meta(nested_meta);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for FeatureVisitor {
+    fn visit_attribute(&mut self, attr: &'ast syn::Attribute) {
+        if attr.path().is_ident("cfg") {
+            if let Ok(meta) = attr.parse_args::<syn::Meta>() {
+                self.visit_cfg_meta(&meta);
+            }
+        } else if attr.path().is_ident("cfg_attr") {
+            // `cfg_attr(feature = "x", derive(Debug), ...)`: only the first argument is the
+            // condition, the rest are the attributes to apply.
+            if let Ok(args) = attr.parse_args_with(
+                syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+            ) {
+                if let Some(condition) = args.first() {
+                    self.visit_cfg_meta(condition);
+                }
+            }
+        }
+        visit::visit_attribute(self, attr);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        if mac.path.is_ident("cfg") {
+            if let Ok(meta) = mac.parse_body::<syn::Meta>() {
+                self.visit_cfg_meta(&meta);
+            }
+        }
+        visit::visit_macro(self, mac);
+    }
+}
+
+/// Scans a single `.rs` file for `feature = "..."` predicates, returning each match as a
+/// `(name, line_number)` pair. Parses the file as a real `syn::File` for accurate results;
+/// falls back to the line-based regex scan for files that fail to parse (e.g. non-UTF8 or
+/// syntactically invalid under the current edition).
+fn scan_rust_file(path: &Path) -> Result<Vec<(String, u64)>, String> {
+    let content = read_to_string(path).map_err(|e| e.to_string())?;
+    match syn::parse_file(&content) {
+        Ok(ast) => {
+            let mut visitor = FeatureVisitor::default();
+            visitor.visit_file(&ast);
+            Ok(visitor.features)
+        }
+        Err(_) => Ok(scan_rust_file_by_line(&content)),
+    }
+}
+
+/// Line-based fallback used when `syn` can't parse the file.
+fn scan_rust_file_by_line(content: &str) -> Vec<(String, u64)> {
+    let mut features = Vec::new();
+    for (line_number, line) in content.lines().enumerate() {
+        if let Some(names) = extract_feature_names(line) {
+            for name in names {
+                features.push((name.to_string(), (line_number + 1) as u64));
+            }
+        }
+    }
+    features
+}
+
+/// Per-file result of scanning for feature usages, keyed by the file it came from: each
+/// `scan_rust_file` call run across the rayon pool returns this, to be merged back into the
+/// mapping single-threaded.
+type ScannedFeatures = Vec<Result<Vec<(PathBuf, String, u64)>, String>>;
+
 /// Struct that represents a feature.
 #[derive(Debug, Clone)]
 pub enum Feature {
@@ -69,23 +313,18 @@ impl Feature {
         }
     }
 
-    /// Returns a clinkable link to the feature inside the code.
-    fn clickable_path(&self) -> Option<String> {
+    /// Returns the name of the feature.
+    fn name(&self) -> &str {
         match self {
-            Self::UsedFeature {
-                path, line_number, ..
-            } => {
-                let clickable_path = format!("{:?}:{}", path, line_number);
-                Some(clickable_path)
-            }
-            Self::ExposedFeature { .. } => None,
+            Self::UsedFeature { name, .. } | Self::ExposedFeature { name } => name,
         }
     }
 
-    /// Returns the name of the feature.
-    fn name(&self) -> &str {
+    /// Returns the line number the feature was found at, if it exists.
+    fn line_number(&self) -> Option<u64> {
         match self {
-            Self::UsedFeature { name, .. } | Self::ExposedFeature { name } => name,
+            Self::UsedFeature { line_number, .. } => Some(*line_number),
+            Self::ExposedFeature { .. } => None,
         }
     }
 }
@@ -101,6 +340,17 @@ struct CrateInfo {
     used_features: HashSet<Feature>,
     // Set that represents the difference between the used features and the exposed features.
     hidden_features: HashSet<Feature>,
+    // Set of feature/dependency names referenced inside another feature's dependency array,
+    // e.g. the "foo" and "bar" in `full = ["foo", "bar"]`. Populated while parsing the
+    // `[features]` table so that umbrella features don't cause their members to be
+    // flagged as orphans.
+    referenced_features: HashSet<String>,
+    // Set that represents the features exposed by this crate but never used in its code,
+    // nor pulled in by another feature's dependency array.
+    orphan_features: HashSet<Feature>,
+    // Feature-dependency-array entries that don't resolve to anything in the manifest: a pair
+    // of (the feature the array belongs to, the offending reference).
+    undefined_feature_references: Vec<(String, String)>,
 }
 
 impl CrateInfo {
@@ -110,11 +360,17 @@ impl CrateInfo {
         let exposed_features = HashSet::new();
         let used_features = HashSet::new();
         let hidden_features = HashSet::new();
+        let referenced_features = HashSet::new();
+        let orphan_features = HashSet::new();
+        let undefined_feature_references = Vec::new();
         Self {
             path,
             exposed_features,
             used_features,
             hidden_features,
+            referenced_features,
+            orphan_features,
+            undefined_feature_references,
         }
     }
 
@@ -124,6 +380,79 @@ impl CrateInfo {
     }
 }
 
+/// Strips the `dep:` and `crate/` sigils off a feature reference found inside another
+/// feature's dependency array, returning the name of the feature/dependency it actually
+/// points to. e.g. `"dep:optional"` -> `"optional"`, `"dep/subfeature"` -> `"subfeature"`.
+fn referenced_feature_name(reference: &str) -> &str {
+    if let Some(stripped) = reference.strip_prefix("dep:") {
+        stripped
+    } else if let Some((_, subfeature)) = reference.split_once('/') {
+        subfeature
+    } else {
+        reference
+    }
+}
+
+/// Collects every dependency name declared across `[dependencies]`, `[dev-dependencies]`,
+/// `[build-dependencies]`, and any `target.*.dependencies` table, along with the subset that
+/// are declared `optional = true` (which implicitly define a feature of the same name).
+fn collect_dependencies(toml: &toml::Value) -> (HashSet<String>, HashSet<String>) {
+    let mut all = HashSet::new();
+    let mut optional = HashSet::new();
+
+    for key in ["dependencies", "dev-dependencies", "build-dependencies"] {
+        if let Some(toml::Value::Table(table)) = toml.get(key) {
+            collect_dependency_table(table, &mut all, &mut optional);
+        }
+    }
+
+    if let Some(toml::Value::Table(targets)) = toml.get("target") {
+        for target_spec in targets.values() {
+            if let Some(toml::Value::Table(table)) = target_spec.get("dependencies") {
+                collect_dependency_table(table, &mut all, &mut optional);
+            }
+        }
+    }
+
+    (all, optional)
+}
+
+/// Records every dependency in a single dependencies table, noting which ones are optional.
+fn collect_dependency_table(
+    table: &toml::value::Table,
+    all: &mut HashSet<String>,
+    optional: &mut HashSet<String>,
+) {
+    for (name, spec) in table.iter() {
+        all.insert(name.clone());
+        if matches!(spec.get("optional"), Some(toml::Value::Boolean(true))) {
+            optional.insert(name.clone());
+        }
+    }
+}
+
+/// Whether a raw entry from a feature's dependency array (e.g. `"other-feature"`,
+/// `"dep/subfeature"`, or `"dep:optional"`) resolves to something actually defined in the
+/// manifest: another feature, an optional dependency (bare or `dep:` form), or any declared
+/// dependency (`dep/subfeature` form).
+fn feature_reference_resolves(
+    reference: &str,
+    exposed_feature_names: &HashSet<&str>,
+    declared_dependencies: &HashSet<String>,
+    optional_dependencies: &HashSet<String>,
+) -> bool {
+    if let Some(dependency) = reference.strip_prefix("dep:") {
+        return optional_dependencies.contains(dependency);
+    }
+    if let Some((dependency, _subfeature)) = reference.split_once('/') {
+        // Weak-dependency syntax, e.g. `serde?/derive`: the `?` only means "don't enable
+        // `serde` on its own", it's still the same dependency name for lookup purposes.
+        let dependency = dependency.strip_suffix('?').unwrap_or(dependency);
+        return declared_dependencies.contains(dependency);
+    }
+    exposed_feature_names.contains(reference) || optional_dependencies.contains(reference)
+}
+
 /// Helper function to determine whether an entry is hidden (starts with '.').
 fn is_hidden(entry: &DirEntry) -> bool {
     if entry.depth() == 0 {
@@ -132,22 +461,23 @@ fn is_hidden(entry: &DirEntry) -> bool {
     entry
         .file_name()
         .to_str()
-        .map_or(false, |s| s.starts_with('.'))
+        .is_some_and(|s| s.starts_with('.'))
 }
 /// A mapping from `PathBuf` to `CrateInfo`. Only crates which USE features in their code will be added.
 #[derive(Debug)]
 pub struct Package {
     mapping: HashMap<PathBuf, CrateInfo>,
 
-    // Set of paths to be ignored.
-    ignored_paths: HashSet<PathBuf>,
+    // Compiled glob patterns of paths to be ignored. A directory matching one of these is
+    // pruned from the walk entirely, rather than visited and discarded.
+    ignored_paths: GlobSet,
 
     // Set of features to be ignored.
     ignored_features: HashSet<String>,
 }
 
 impl Package {
-    pub fn new(ignored_paths: HashSet<PathBuf>, ignored_features: HashSet<String>) -> Self {
+    pub fn new(ignored_paths: GlobSet, ignored_features: HashSet<String>) -> Self {
         Self {
             mapping: HashMap::new(),
             ignored_paths,
@@ -174,43 +504,64 @@ impl Package {
     /// Finds the used features by ripgrep'ing the path, looking for occurences of the pattern "feature = ".
     /// Then groups those occurences by crates.
     pub fn find_used_features(&mut self, path: &Path) -> Result<(), String> {
+        // First, collect every candidate `.rs` path single-threaded: the walk itself (and the
+        // hidden/glob pruning) is cheap compared to parsing file contents, so there's nothing
+        // to gain from parallelizing it.
         let walker = WalkDir::new(path).into_iter();
-        for entry in walker.filter_entry(|e| !is_hidden(e)) {
+        let mut rust_files = Vec::new();
+        let mut cargo_manifests = Vec::new();
+        for entry in
+            walker.filter_entry(|e| !is_hidden(e) && !self.ignored_paths.is_match(e.path()))
+        {
             let entry = entry.map_err(|e| e.to_string())?;
             let entry_path = entry.path();
-            // If the entry path figures amongst the list of ignored paths, then skip it.
-            if self.ignored_paths.contains(entry_path) {
-                continue;
-            }
             let is_rust_file = entry_path
                 .extension()
-                .map_or(false, |ext| ext.to_str().map_or(false, |s| s == "rs"));
+                .is_some_and(|ext| ext.to_str() == Some("rs"));
             // We only wish to parse .rs files!
             if is_rust_file {
-                let file = File::open(entry.path()).map_err(|e| e.to_string())?;
-                let lines = BufReader::new(file).lines();
-                let path_buf = entry_path.to_path_buf();
-                // Go through every line of the file.
-                for (line_number, line) in lines.enumerate() {
-                    // Make sure the line is an acceptable `String`.
-                    if let Ok(line) = line {
-                        // Extract the feature names.
-                        let feature_names = extract_feature_names(&line);
-
-                        // If we found some features, add them!
-                        if let Some(f) = feature_names {
-                            for feature_name in f {
-                                if !self.ignored_features.contains(feature_name) {
-                                    let feature = Feature::UsedFeature {
-                                        name: feature_name.to_string(),
-                                        path: path_buf.clone(),
-                                        line_number: line_number as u64,
-                                    };
-                                    self.add_feature(feature)?
-                                }
-                            }
-                        }
-                    }
+                rust_files.push(entry_path.to_path_buf());
+            } else if entry_path.file_name().is_some_and(|name| name == "Cargo.toml") {
+                cargo_manifests.push(entry_path.to_path_buf());
+            }
+        }
+
+        // Seed the mapping with every manifest we found, even ones whose crate never triggers
+        // `add_feature` (i.e. uses zero features in code). Otherwise a crate that declares
+        // features but uses none of them is never added to the mapping at all, and its orphan
+        // features silently never get reported.
+        for cargo_manifest in cargo_manifests {
+            self.mapping
+                .entry(cargo_manifest.clone())
+                .or_insert_with(|| CrateInfo::new(&cargo_manifest));
+        }
+
+        // Then scan file contents in parallel. Each worker returns plain (path, name, line)
+        // tuples rather than mutating `self` directly: `add_feature` resolves the associated
+        // Cargo.toml and mutates the shared mapping, so doing that per-feature across threads
+        // would mean locking on every single match. Instead we merge everything in a single
+        // single-threaded pass below.
+        let scanned: ScannedFeatures = rust_files
+            .par_iter()
+            .map(|rust_file| {
+                scan_rust_file(rust_file).map(|features| {
+                    features
+                        .into_iter()
+                        .map(|(name, line_number)| (rust_file.clone(), name, line_number))
+                        .collect()
+                })
+            })
+            .collect();
+
+        for result in scanned {
+            for (path, feature_name, line_number) in result? {
+                if !self.ignored_features.contains(&feature_name) {
+                    let feature = Feature::UsedFeature {
+                        name: feature_name,
+                        path,
+                        line_number,
+                    };
+                    self.add_feature(feature)?
                 }
             }
         }
@@ -221,13 +572,13 @@ impl Package {
     pub fn add_feature(&mut self, feature: Feature) -> Result<(), String> {
         let path = feature
             .path()
-            .ok_or_else(|| "internal error: should have a path")?;
+            .ok_or("internal error: should have a path")?;
         // The path to the parent directory
-        let parent = path.parent().ok_or_else(|| "path has no parent")?;
+        let parent = path.parent().ok_or("path has no parent")?;
         // Create a Cargo.toml path candidate: a Cargo file that would be in the same directory as the .rs file we just matched.
         let cargo_path = self
-            .find_associated_cargo(&parent)
-            .ok_or_else(|| "could not find corresponding Cargo file")?;
+            .find_associated_cargo(parent)
+            .ok_or("could not find corresponding Cargo file")?;
 
         if let Some(crate_info) = self.mapping.get_mut(&cargo_path) {
             // This crate is already in the map, so simply add the feature to the list of used features.
@@ -247,28 +598,100 @@ impl Package {
     }
 
     /// Finds the exposed features of every Cargo.toml file in the mapping.
-    pub fn find_exposed_features(&mut self) {
+    pub fn find_exposed_features(&mut self) -> Result<(), String> {
         // Iterate over every Cargo.
         for v in self.mapping.values_mut() {
-            // Load its content in a String. Using unwrap because we want our program to stop in case of an error.
-            let s = read_to_string(&v.path).unwrap();
-            // Parse the Cargo into a TOML structure. Using unwrap because we want our program to stop in case of an error.
-            let toml = s.parse::<toml::Value>().unwrap();
+            // Load its content in a String.
+            let s = read_to_string(&v.path)
+                .map_err(|e| format!("{}: {}", v.path.display(), e))?;
+            // Parse the Cargo into a TOML structure.
+            let toml = s
+                .parse::<toml::Value>()
+                .map_err(|e| format!("{}: {}", v.path.display(), e))?;
             let table = match &toml.get("features") {
                 Some(toml::Value::Table(table)) => Some(table),
                 _ => None,
             };
             let mut exposed = HashSet::new();
+            let mut referenced = HashSet::new();
+            // Every feature name declared in `[features]`, regardless of `--ignored-features`.
+            // `ignored_features` only suppresses reporting (see `exposed` below); it must not
+            // make a dependency-array reference to an ignored feature look undefined.
+            let mut all_feature_names: HashSet<String> = HashSet::new();
+            // Feature name -> raw entries in its dependency array, kept around so they can be
+            // validated once the full set of exposed features and dependencies is known.
+            let mut feature_arrays: Vec<(String, Vec<String>)> = Vec::new();
             if let Some(table) = table {
-                for (feature_name, _) in table.iter() {
+                for (feature_name, dependencies) in table.iter() {
                     let name = feature_name.to_string();
+                    all_feature_names.insert(name.clone());
                     // Make sure the feature is not one of the ignored features.
                     if !self.ignored_features.contains(&name) {
-                        exposed.insert(Feature::ExposedFeature { name });
+                        exposed.insert(Feature::ExposedFeature { name: name.clone() });
                     };
+                    // Record every feature/dependency this feature pulls in, so that umbrella
+                    // features (e.g. `full = ["foo", "bar"]`) don't make "foo" and "bar" look
+                    // orphaned even though they're never referenced directly in the code.
+                    if let toml::Value::Array(dependencies) = dependencies {
+                        let raw: Vec<String> = dependencies
+                            .iter()
+                            .filter_map(|dependency| match dependency {
+                                toml::Value::String(dependency) => Some(dependency.clone()),
+                                _ => None,
+                            })
+                            .collect();
+                        for dependency in &raw {
+                            referenced.insert(referenced_feature_name(dependency).to_string());
+                        }
+                        feature_arrays.push((name, raw));
+                    }
+                }
+            }
+
+            // Now that we know every feature this crate exposes, and every dependency it
+            // declares (which define an implicit feature when optional), validate that each
+            // feature's dependency array only references things that actually exist.
+            let (declared_dependencies, optional_dependencies) = collect_dependencies(&toml);
+            let mut undefined_feature_references = Vec::new();
+            {
+                let exposed_names: HashSet<&str> =
+                    all_feature_names.iter().map(String::as_str).collect();
+                for (feature_name, raw_refs) in &feature_arrays {
+                    for reference in raw_refs {
+                        if !feature_reference_resolves(
+                            reference,
+                            &exposed_names,
+                            &declared_dependencies,
+                            &optional_dependencies,
+                        ) {
+                            undefined_feature_references
+                                .push((feature_name.clone(), reference.clone()));
+                        }
+                    }
                 }
             }
+
             v.exposed_features = exposed;
+            v.referenced_features = referenced;
+            v.undefined_feature_references = undefined_feature_references;
+        }
+        Ok(())
+    }
+
+    /// Finds the orphan features, i.e. features declared in a Cargo.toml's `[features]` table
+    /// but never referenced anywhere in that crate's sources, and not reachable from another
+    /// feature's dependency array either.
+    pub fn find_orphan_features(&mut self) {
+        // Iterate over the package's crates.
+        for crate_info in self.mapping.values_mut() {
+            // Find the features exposed but not used, then discard the ones reachable from
+            // another feature's dependency array.
+            crate_info.orphan_features = crate_info
+                .exposed_features
+                .difference(&crate_info.used_features)
+                .filter(|feature| !crate_info.referenced_features.contains(feature.name()))
+                .cloned()
+                .collect();
         }
     }
 
@@ -285,28 +708,202 @@ impl Package {
         }
     }
 
+    /// Builds a structured report of the hidden features found across the package. Building
+    /// this doesn't print anything or decide pass/fail, so the same report can be handed to
+    /// either the human or the JSON printer, and the exit-code decision can be made
+    /// independently of how (or whether) it gets printed.
+    pub fn hidden_features_report(&self) -> HiddenFeaturesReport {
+        let crates = self
+            .mapping
+            .values()
+            .filter(|cargo| !cargo.hidden_features.is_empty())
+            .map(|cargo| CrateReport {
+                cargo_toml: cargo.path.clone(),
+                hidden_features: cargo
+                    .hidden_features
+                    .iter()
+                    .map(|feature| HiddenFeatureEntry {
+                        name: feature.name().to_string(),
+                        file: feature.path().map(Path::to_path_buf),
+                        line: feature.line_number(),
+                    })
+                    .collect(),
+            })
+            .collect();
+        HiddenFeaturesReport { crates }
+    }
+
+    /// Prints a hidden-features report as human-readable text.
+    fn print_hidden_features_human(report: &HiddenFeaturesReport) {
+        for cargo in &report.crates {
+            println!("path: {:?}", cargo.cargo_toml);
+            for feature in &cargo.hidden_features {
+                let clickable_path = match (&feature.file, feature.line) {
+                    (Some(file), Some(line)) => format!("{:?}:{}", file, line),
+                    _ => feature.name.clone(),
+                };
+                println!("\t{}\t{}", feature.name, clickable_path);
+            }
+        }
+    }
+
+    /// Prints a hidden-features report as a single JSON object.
+    fn print_hidden_features_json(report: &HiddenFeaturesReport) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(report).map_err(|e| e.to_string())?;
+        println!("{}", json);
+        Ok(())
+    }
+
     // todo pretty print
-    pub fn check_hidden_features(&self) -> Result<(), String> {
-        let mut empty = true;
-        for cargo in self.mapping.values() {
-            if !cargo.hidden_features.is_empty() {
-                empty = false;
-                println!("path: {:?}", cargo.path);
+    pub fn check_hidden_features(&self, format: Format) -> Result<(), String> {
+        let report = self.hidden_features_report();
+        match format {
+            Format::Human => Self::print_hidden_features_human(&report),
+            Format::Json => Self::print_hidden_features_json(&report)?,
+        }
+        if report.is_empty() {
+            Ok(())
+        } else {
+            Err("Hidden features detected.".to_string())
+        }
+    }
+
+    /// Builds a structured report of the orphan features found across the package, mirroring
+    /// `hidden_features_report`.
+    pub fn orphan_features_report(&self) -> OrphanFeaturesReport {
+        let crates = self
+            .mapping
+            .values()
+            .filter(|cargo| !cargo.orphan_features.is_empty())
+            .map(|cargo| CrateOrphanReport {
+                cargo_toml: cargo.path.clone(),
+                orphan_features: cargo
+                    .orphan_features
+                    .iter()
+                    .map(|feature| OrphanFeatureEntry {
+                        name: feature.name().to_string(),
+                    })
+                    .collect(),
+            })
+            .collect();
+        OrphanFeaturesReport { crates }
+    }
+
+    /// Prints an orphan-features report as human-readable text.
+    fn print_orphan_features_human(report: &OrphanFeaturesReport) {
+        for cargo in &report.crates {
+            println!("path: {:?}", cargo.cargo_toml);
+            for feature in &cargo.orphan_features {
+                println!("\t{}", feature.name);
             }
-            for feature in &cargo.hidden_features {
+        }
+    }
+
+    /// Prints an orphan-features report as a single JSON object.
+    fn print_orphan_features_json(report: &OrphanFeaturesReport) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(report).map_err(|e| e.to_string())?;
+        println!("{}", json);
+        Ok(())
+    }
+
+    pub fn check_orphan_features(&self, format: Format) -> Result<(), String> {
+        let report = self.orphan_features_report();
+        match format {
+            Format::Human => Self::print_orphan_features_human(&report),
+            Format::Json => Self::print_orphan_features_json(&report)?,
+        }
+        if report.is_empty() {
+            Ok(())
+        } else {
+            Err("Orphan features detected.".to_string())
+        }
+    }
+
+    /// Builds a structured report of the undefined feature references found across the
+    /// package, mirroring `hidden_features_report`.
+    pub fn feature_references_report(&self) -> FeatureReferencesReport {
+        let crates = self
+            .mapping
+            .values()
+            .filter(|cargo| !cargo.undefined_feature_references.is_empty())
+            .map(|cargo| CrateReferenceReport {
+                cargo_toml: cargo.path.clone(),
+                undefined_references: cargo
+                    .undefined_feature_references
+                    .iter()
+                    .map(|(feature, reference)| UndefinedReferenceEntry {
+                        feature: feature.clone(),
+                        reference: reference.clone(),
+                    })
+                    .collect(),
+            })
+            .collect();
+        FeatureReferencesReport { crates }
+    }
+
+    /// Prints an undefined-feature-reference report as human-readable text.
+    fn print_feature_references_human(report: &FeatureReferencesReport) {
+        for cargo in &report.crates {
+            println!("path: {:?}", cargo.cargo_toml);
+            for reference in &cargo.undefined_references {
                 println!(
-                    "\t{}\t{}",
-                    feature.name(),
-                    feature
-                        .clickable_path()
-                        .unwrap_or_else(|| String::from(feature.name()))
+                    "\tfeature \"{}\" references undefined \"{}\"",
+                    reference.feature, reference.reference
                 );
             }
         }
-        if empty {
+    }
+
+    /// Prints an undefined-feature-reference report as a single JSON object.
+    fn print_feature_references_json(report: &FeatureReferencesReport) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(report).map_err(|e| e.to_string())?;
+        println!("{}", json);
+        Ok(())
+    }
+
+    pub fn check_feature_references(&self, format: Format) -> Result<(), String> {
+        let report = self.feature_references_report();
+        match format {
+            Format::Human => Self::print_feature_references_human(&report),
+            Format::Json => Self::print_feature_references_json(&report)?,
+        }
+        if report.is_empty() {
             Ok(())
         } else {
-            Err("Hidden features detected.".to_string())
+            Err("Undefined feature references detected.".to_string())
+        }
+    }
+
+    /// Runs the hidden-features, (optionally) orphan-features and feature-reference checks
+    /// together and reports the results. Under `Format::Human` each check still prints its own
+    /// section, same as calling them individually; under `Format::Json` their reports are
+    /// combined and printed as a single JSON document, so CI jobs parsing `--format json` stdout
+    /// don't see more than one JSON object back to back.
+    pub fn check_all(&self, format: Format, include_orphans: bool) -> Result<(), String> {
+        match format {
+            Format::Human => {
+                let hidden_result = self.check_hidden_features(format);
+                if include_orphans {
+                    self.check_orphan_features(format)?;
+                }
+                self.check_feature_references(format)?;
+                hidden_result
+            }
+            Format::Json => {
+                let full = FullReport {
+                    hidden_features: self.hidden_features_report(),
+                    orphan_features: include_orphans.then(|| self.orphan_features_report()),
+                    feature_references: self.feature_references_report(),
+                };
+                let ok = full.is_ok();
+                let json = serde_json::to_string_pretty(&full).map_err(|e| e.to_string())?;
+                println!("{}", json);
+                if ok {
+                    Ok(())
+                } else {
+                    Err("Issues detected.".to_string())
+                }
+            }
         }
     }
 
@@ -323,11 +920,24 @@ impl Package {
         res
     }
 
+    #[cfg(test)]
+    /// Returns a set of all the orphan feature names.
+    /// Used for testing purposes.
+    pub fn orphan_features(&self) -> HashSet<&str> {
+        let mut res = HashSet::new();
+        for cargo in self.mapping.values() {
+            for feature in &cargo.orphan_features {
+                res.insert(feature.name());
+            }
+        }
+        res
+    }
+
     #[cfg(test)]
     pub fn find_and_check(&mut self, path: &Path) -> Result<(), String> {
         self.find_used_features(path)?;
-        self.find_exposed_features();
+        self.find_exposed_features()?;
         self.find_hidden_features();
-        self.check_hidden_features()
+        self.check_hidden_features(Format::Human)
     }
 }
@@ -5,56 +5,60 @@ mod tests {
     const ONE_FEATURE_FILE: &str = "test_files/one_feature.rs";
     const FOUR_FEATURES_FILE: &str = "test_files/four_features.rs";
     const ONE_LINE_FEATURES_FILE: &str = "test_files/one_line_features.rs";
-    use crate::package::Package;
+    const WEAK_DEP_CRATE_DIR: &str = "test_files/weak_dep_crate";
+    const ORPHAN_CRATE_DIR: &str = "test_files/orphan_crate";
+    const PRUNED_DIR: &str = "test_files/pruned_dir";
+    const SYN_PARSING_FILE: &str = "test_files/syn_parsing.rs";
+    const REGEX_FALLBACK_FILE: &str = "test_files/regex_fallback.rs";
+    const UNDEFINED_REFERENCE_CRATE_DIR: &str = "test_files/undefined_reference_crate";
+    const PARALLEL_SCAN_DIR: &str = "test_files/parallel_scan";
+    const INVALID_MANIFEST_TREE_DIR: &str = "test_files/invalid_manifest_tree";
+    const IGNORED_FEATURE_REFERENCE_CRATE_DIR: &str = "test_files/ignored_feature_reference_crate";
+    const INCLUDE_ROOT_A_DIR: &str = "test_files/include_roots_dir/root_a";
+    use crate::package::{Format, Package};
+    use globset::{Glob, GlobSet, GlobSetBuilder};
     use std::collections::HashSet;
-    use std::path::{Path, PathBuf};
-
-    fn find_and_check(package: &mut Package, path: &Path) -> Result<(), String> {
-        package.find_used_features(path)?;
-        package.find_exposed_features();
-        package.find_hidden_features();
-        package.check_hidden_features()
-    }
+    use std::path::PathBuf;
 
     #[test]
     fn empty_features() {
-        let excluded_paths = HashSet::new();
+        let excluded_paths = GlobSet::empty();
         let excluded_features = HashSet::new();
         let p = Package::new(excluded_paths, excluded_features);
-        let res = p.check_hidden_features();
+        let res = p.check_hidden_features(Format::Human);
         dbg!(&res);
         assert!(res.is_ok());
     }
 
     #[test]
     fn no_features() {
-        let excluded_paths = HashSet::new();
+        let excluded_paths = GlobSet::empty();
         let excluded_features = HashSet::new();
         let mut p = Package::new(excluded_paths, excluded_features);
         let path = PathBuf::from(NO_FEATURES_FILE);
-        let res = find_and_check(&mut p, &path);
+        let res = p.find_and_check(&path);
         dbg!(&res);
         assert!(res.is_ok());
     }
 
     #[test]
     fn does_not_exist() {
-        let excluded_paths = HashSet::new();
+        let excluded_paths = GlobSet::empty();
         let excluded_features = HashSet::new();
         let mut p = Package::new(excluded_paths, excluded_features);
         let path = PathBuf::new();
-        let res = find_and_check(&mut p, &path);
+        let res = p.find_and_check(&path);
         dbg!(&res);
         assert!(res.is_err());
     }
 
     #[test]
     fn one_feature() {
-        let excluded_paths = HashSet::new();
+        let excluded_paths = GlobSet::empty();
         let excluded_features = HashSet::new();
         let mut p = Package::new(excluded_paths, excluded_features);
         let path = PathBuf::from(ONE_FEATURE_FILE);
-        let res = find_and_check(&mut p, &path);
+        let res = p.find_and_check(&path);
         let features = p.hidden_features();
         assert!(features.contains("hidden-feature"));
         dbg!(&res);
@@ -63,12 +67,12 @@ mod tests {
 
     #[test]
     fn one_feature_but_excluded() {
-        let excluded_paths = HashSet::new();
+        let excluded_paths = GlobSet::empty();
         let mut excluded_features = HashSet::new();
         excluded_features.insert(String::from("hidden-feature"));
         let mut p = Package::new(excluded_paths, excluded_features);
         let path = PathBuf::from(ONE_FEATURE_FILE);
-        let res = find_and_check(&mut p, &path);
+        let res = p.find_and_check(&path);
         let features = p.hidden_features();
         assert!(features.is_empty());
         dbg!(&res);
@@ -77,12 +81,13 @@ mod tests {
 
     #[test]
     fn one_feature_but_path_excluded() {
-        let mut excluded_paths = HashSet::new();
-        excluded_paths.insert(PathBuf::from(ONE_FEATURE_FILE));
+        let mut builder = GlobSetBuilder::new();
+        builder.add(Glob::new(ONE_FEATURE_FILE).unwrap());
+        let excluded_paths = builder.build().unwrap();
         let excluded_features = HashSet::new();
         let mut p = Package::new(excluded_paths, excluded_features);
         let path = PathBuf::from(ONE_FEATURE_FILE);
-        let res = find_and_check(&mut p, &path);
+        let res = p.find_and_check(&path);
         let features = p.hidden_features();
         assert!(features.is_empty());
         dbg!(&res);
@@ -91,11 +96,11 @@ mod tests {
 
     #[test]
     fn four_features() {
-        let excluded_paths = HashSet::new();
+        let excluded_paths = GlobSet::empty();
         let excluded_features = HashSet::new();
         let mut p = Package::new(excluded_paths, excluded_features);
         let path = PathBuf::from(FOUR_FEATURES_FILE);
-        let res = find_and_check(&mut p, &path);
+        let res = p.find_and_check(&path);
         let mut features = p.hidden_features();
         dbg!(&features);
         assert!(features.remove("hidden-feature-1"));
@@ -109,11 +114,11 @@ mod tests {
 
     #[test]
     fn one_line_features() {
-        let excluded_paths = HashSet::new();
+        let excluded_paths = GlobSet::empty();
         let excluded_features = HashSet::new();
         let mut p = Package::new(excluded_paths, excluded_features);
         let path = PathBuf::from(ONE_LINE_FEATURES_FILE);
-        let res = find_and_check(&mut p, &path);
+        let res = p.find_and_check(&path);
         let mut features = p.hidden_features();
         dbg!(&features);
         assert!(features.remove("get-your"));
@@ -124,4 +129,179 @@ mod tests {
         dbg!(&res);
         assert!(res.is_err());
     }
+
+    #[test]
+    fn weak_dependency_reference_resolves() {
+        let excluded_paths = GlobSet::empty();
+        let excluded_features = HashSet::new();
+        let mut p = Package::new(excluded_paths, excluded_features);
+        let path = PathBuf::from(WEAK_DEP_CRATE_DIR);
+        p.find_used_features(&path).unwrap();
+        p.find_exposed_features().unwrap();
+        let res = p.check_feature_references(Format::Human);
+        dbg!(&res);
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn ignored_path_glob_prunes_the_whole_subtree() {
+        // "sub" is gitignore-style-excluded via a glob matching the directory itself, so
+        // hidden.rs inside it should never even be walked into, while kept.rs (a sibling) still
+        // gets scanned normally.
+        let mut builder = GlobSetBuilder::new();
+        builder.add(Glob::new("test_files/pruned_dir/sub").unwrap());
+        let excluded_paths = builder.build().unwrap();
+        let excluded_features = HashSet::new();
+        let mut p = Package::new(excluded_paths, excluded_features);
+        let path = PathBuf::from(PRUNED_DIR);
+        p.find_used_features(&path).unwrap();
+        let features = p.hidden_features();
+        dbg!(&features);
+        assert!(!features.contains("pruned-feature"));
+        assert!(features.contains("kept-feature"));
+    }
+
+    #[test]
+    fn syn_parsing_handles_multiline_cfg_attr_and_cfg_macro() {
+        let excluded_paths = GlobSet::empty();
+        let excluded_features = HashSet::new();
+        let mut p = Package::new(excluded_paths, excluded_features);
+        let path = PathBuf::from(SYN_PARSING_FILE);
+        p.find_used_features(&path).unwrap();
+        let mut features = p.hidden_features();
+        dbg!(&features);
+        assert!(features.remove("multiline-feature"));
+        assert!(features.remove("cfgattr-feature"));
+        assert!(features.remove("cfgmacro-feature"));
+        // Parsed as a real AST, so a string literal that merely looks like a feature predicate
+        // must not be picked up.
+        assert!(!features.contains("fake-feature"));
+    }
+
+    #[test]
+    fn regex_fallback_scans_files_syn_cannot_parse() {
+        let excluded_paths = GlobSet::empty();
+        let excluded_features = HashSet::new();
+        let mut p = Package::new(excluded_paths, excluded_features);
+        let path = PathBuf::from(REGEX_FALLBACK_FILE);
+        p.find_used_features(&path).unwrap();
+        let features = p.hidden_features();
+        dbg!(&features);
+        assert!(features.contains("fallback-feature"));
+    }
+
+    #[test]
+    fn json_format_reports_every_check_in_one_pass() {
+        // check_all must fold every check's findings into a single JSON document rather than
+        // printing one JSON blob per check, or CI jobs parsing --format json stdout would choke
+        // on the extra objects.
+        let excluded_paths = GlobSet::empty();
+        let excluded_features = HashSet::new();
+        let mut p = Package::new(excluded_paths, excluded_features);
+        let path = PathBuf::from(ORPHAN_CRATE_DIR);
+        p.find_used_features(&path).unwrap();
+        p.find_exposed_features().unwrap();
+        p.find_hidden_features();
+        p.find_orphan_features();
+        let res = p.check_all(Format::Json, true);
+        dbg!(&res);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn parallel_scan_aggregates_every_file() {
+        // The rayon-parallelized scan in find_used_features merges results from many files back
+        // into a single-threaded pass; make sure nothing gets dropped along the way.
+        let excluded_paths = GlobSet::empty();
+        let excluded_features = HashSet::new();
+        let mut p = Package::new(excluded_paths, excluded_features);
+        let path = PathBuf::from(PARALLEL_SCAN_DIR);
+        p.find_used_features(&path).unwrap();
+        let mut features = p.hidden_features();
+        dbg!(&features);
+        for i in 1..=5 {
+            assert!(features.remove(&*format!("parallel-feature-{}", i)));
+        }
+        assert!(features.is_empty());
+    }
+
+    #[test]
+    fn undefined_feature_reference_is_reported() {
+        let excluded_paths = GlobSet::empty();
+        let excluded_features = HashSet::new();
+        let mut p = Package::new(excluded_paths, excluded_features);
+        let path = PathBuf::from(UNDEFINED_REFERENCE_CRATE_DIR);
+        p.find_used_features(&path).unwrap();
+        p.find_exposed_features().unwrap();
+        let res = p.check_feature_references(Format::Human);
+        dbg!(&res);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn reference_to_an_ignored_feature_is_not_undefined() {
+        // Regression test: `--ignored-features` only suppresses a feature from the hidden/orphan
+        // reports, it must not make a dependency-array entry referencing that feature look
+        // undefined. `big = ["small"]` with "small" ignored used to report "big" as referencing
+        // an undefined feature.
+        let excluded_paths = GlobSet::empty();
+        let mut excluded_features = HashSet::new();
+        excluded_features.insert(String::from("small"));
+        let mut p = Package::new(excluded_paths, excluded_features);
+        let path = PathBuf::from(IGNORED_FEATURE_REFERENCE_CRATE_DIR);
+        p.find_used_features(&path).unwrap();
+        p.find_exposed_features().unwrap();
+        let res = p.check_feature_references(Format::Human);
+        dbg!(&res);
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn scanning_one_include_root_never_picks_up_a_sibling_roots_features() {
+        // `--include-paths` walks each given root directly instead of walking a shared parent
+        // and pruning afterwards, so a sibling subtree's features must never show up at all,
+        // not just get filtered out of the report.
+        let excluded_paths = GlobSet::empty();
+        let excluded_features = HashSet::new();
+        let mut p = Package::new(excluded_paths, excluded_features);
+        let path = PathBuf::from(INCLUDE_ROOT_A_DIR);
+        p.find_used_features(&path).unwrap();
+        let features = p.hidden_features();
+        dbg!(&features);
+        assert!(features.contains("root-a-feature"));
+        assert!(!features.contains("root-b-feature"));
+    }
+
+    #[test]
+    fn invalid_manifest_elsewhere_in_tree_is_a_clean_error_not_a_panic() {
+        // Regression test: since find_used_features seeds the mapping from every Cargo.toml
+        // found during the walk, find_exposed_features now reads manifests that were never
+        // touched by any feature usage. A malformed one anywhere in the tree used to unwrap()
+        // and panic the whole run instead of failing gracefully.
+        let excluded_paths = GlobSet::empty();
+        let excluded_features = HashSet::new();
+        let mut p = Package::new(excluded_paths, excluded_features);
+        let path = PathBuf::from(INVALID_MANIFEST_TREE_DIR);
+        p.find_used_features(&path).unwrap();
+        let res = p.find_exposed_features();
+        dbg!(&res);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn orphan_feature_with_no_code_usage_is_reported() {
+        // Regression test: a crate that declares a feature in Cargo.toml but never uses it
+        // anywhere in its sources used to never even be added to the mapping, so its orphan
+        // features were silently never reported.
+        let excluded_paths = GlobSet::empty();
+        let excluded_features = HashSet::new();
+        let mut p = Package::new(excluded_paths, excluded_features);
+        let path = PathBuf::from(ORPHAN_CRATE_DIR);
+        p.find_used_features(&path).unwrap();
+        p.find_exposed_features().unwrap();
+        p.find_orphan_features();
+        let features = p.orphan_features();
+        dbg!(&features);
+        assert!(features.contains("never-used"));
+    }
 }
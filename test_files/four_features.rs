@@ -0,0 +1,11 @@
+#[cfg(feature = "hidden-feature-1")]
+fn one() {}
+
+#[cfg(feature = "hidden-feature-2")]
+fn two() {}
+
+#[cfg(feature = "hidden-feature-3")]
+fn three() {}
+
+#[cfg(feature = "hidden-feature-4")]
+fn four() {}
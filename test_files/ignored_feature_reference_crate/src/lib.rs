@@ -0,0 +1,2 @@
+#[cfg(feature = "big")]
+fn gated() {}
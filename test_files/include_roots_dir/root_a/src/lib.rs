@@ -0,0 +1,2 @@
+#[cfg(feature = "root-a-feature")]
+fn gated() {}
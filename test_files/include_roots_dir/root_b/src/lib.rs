@@ -0,0 +1,2 @@
+#[cfg(feature = "root-b-feature")]
+fn gated() {}
@@ -0,0 +1,2 @@
+#[cfg(feature = "known-feature")]
+fn gated() {}
@@ -0,0 +1,4 @@
+// A file with no feature-gated code at all.
+fn main() {
+    println!("hello, world!");
+}
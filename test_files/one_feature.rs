@@ -0,0 +1,2 @@
+#[cfg(feature = "hidden-feature")]
+fn gated() {}
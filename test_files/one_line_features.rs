@@ -0,0 +1,2 @@
+#[cfg(any(feature = "get-your", feature = "shit-together", feature = "get-it-all-together", feature = "and-put-it-all-in-a-backpack"))]
+fn backpack() {}
@@ -0,0 +1,2 @@
+// No feature-gated code at all: "never-used" in Cargo.toml is a pure orphan.
+pub fn hello() {}
@@ -0,0 +1,2 @@
+#[cfg(feature = "parallel-feature-1")]
+fn gated_1() {}
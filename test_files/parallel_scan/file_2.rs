@@ -0,0 +1,2 @@
+#[cfg(feature = "parallel-feature-2")]
+fn gated_2() {}
@@ -0,0 +1,2 @@
+#[cfg(feature = "parallel-feature-3")]
+fn gated_3() {}
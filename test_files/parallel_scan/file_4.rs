@@ -0,0 +1,2 @@
+#[cfg(feature = "parallel-feature-4")]
+fn gated_4() {}
@@ -0,0 +1,2 @@
+#[cfg(feature = "parallel-feature-5")]
+fn gated_5() {}
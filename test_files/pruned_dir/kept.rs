@@ -0,0 +1,2 @@
+#[cfg(feature = "kept-feature")]
+fn gated() {}
@@ -0,0 +1,2 @@
+#[cfg(feature = "pruned-feature")]
+fn gated() {}
@@ -0,0 +1,4 @@
+// Deliberately invalid Rust syntax (unbalanced parens) so `syn::parse_file` fails and the
+// line-based regex fallback is what actually finds this feature.
+#[cfg(feature = "fallback-feature"
+fn broken( {
@@ -0,0 +1,17 @@
+#[cfg(
+    feature = "multiline-feature"
+)]
+fn multiline() {}
+
+#[cfg_attr(feature = "cfgattr-feature", derive(Debug))]
+struct CfgAttrStruct;
+
+fn uses_cfg_macro() {
+    if cfg!(feature = "cfgmacro-feature") {
+        println!("on");
+    }
+}
+
+fn contains_string_literal_lookalike() -> &'static str {
+    "feature = \"fake-feature\""
+}
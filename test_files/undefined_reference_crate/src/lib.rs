@@ -0,0 +1,2 @@
+#[cfg(feature = "broken")]
+fn gated() {}
@@ -0,0 +1,2 @@
+#[cfg(feature = "derive-support")]
+fn use_derive() {}